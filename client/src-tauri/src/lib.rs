@@ -4,7 +4,7 @@ use std::sync::Arc;
 use std::sync::atomic::Ordering;
 use tauri::State;
 use tokio::sync::Mutex;
-use websocket::WebSocketState;
+use websocket::{LoadTestState, WebSocketState};
 
 // Wrapper for managing the WebSocket task
 struct WsTaskHandle(Mutex<Option<tokio::task::JoinHandle<()>>>);
@@ -15,6 +15,11 @@ async fn connect_rust_ws(
     state: State<'_, Arc<WebSocketState>>,
     task_handle: State<'_, WsTaskHandle>,
     url: String,
+    max_retries: Option<u32>,
+    base_delay_ms: Option<u64>,
+    max_delay_ms: Option<u64>,
+    symbols: Option<Vec<String>>,
+    sampling_mode: Option<websocket::SamplingMode>,
 ) -> Result<(), String> {
     // Check if already running
     if state.running.load(Ordering::SeqCst) {
@@ -22,8 +27,22 @@ async fn connect_rust_ws(
     }
 
     let state_clone = state.inner().clone();
+    let max_retries = max_retries.unwrap_or(0); // 0 = retry until stopped
+    let base_delay_ms = base_delay_ms.unwrap_or(250);
+    let max_delay_ms = max_delay_ms.unwrap_or(30_000);
+    let sampling_mode = sampling_mode.unwrap_or_default();
     let handle = tokio::spawn(async move {
-        websocket::connect_websocket(app_handle, state_clone, url).await;
+        websocket::connect_websocket(
+            app_handle,
+            state_clone,
+            url,
+            max_retries,
+            base_delay_ms,
+            max_delay_ms,
+            symbols,
+            sampling_mode,
+        )
+        .await;
     });
 
     *task_handle.0.lock().await = Some(handle);
@@ -60,20 +79,58 @@ fn get_test_mode() -> String {
     std::env::var("TICK_BENCH_MODE").unwrap_or_else(|_| "js".to_string())
 }
 
+#[tauri::command]
+async fn start_load_test(
+    app_handle: tauri::AppHandle,
+    state: State<'_, Arc<LoadTestState>>,
+    url: String,
+    connections: usize,
+    warmup_secs: u64,
+    sample_rate_secs: u64,
+    binary: bool,
+    payload_kb: Option<usize>,
+) -> Result<(), String> {
+    if state.running.load(Ordering::SeqCst) {
+        return Err("Load test already running".to_string());
+    }
+
+    let config = websocket::LoadTestConfig {
+        url,
+        connections,
+        warmup_secs,
+        sample_rate_secs,
+        binary,
+        payload_kb,
+    };
+    let state_clone = state.inner().clone();
+    websocket::start_load_test(app_handle, state_clone, config).await;
+    Ok(())
+}
+
+#[tauri::command]
+async fn stop_load_test(state: State<'_, Arc<LoadTestState>>) -> Result<(), String> {
+    websocket::stop_load_test(state.inner().clone()).await;
+    Ok(())
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     let ws_state = Arc::new(WebSocketState::new());
     let task_handle = WsTaskHandle(Mutex::new(None));
+    let load_test_state = Arc::new(LoadTestState::new());
 
     tauri::Builder::default()
         .manage(ws_state)
         .manage(task_handle)
+        .manage(load_test_state)
         .invoke_handler(tauri::generate_handler![
             connect_rust_ws,
             disconnect_rust_ws,
             reset_rust_metrics,
             is_rust_ws_connected,
             get_test_mode,
+            start_load_test,
+            stop_load_test,
         ])
         .setup(|app| {
             if cfg!(debug_assertions) {
@@ -1,10 +1,14 @@
+use futures_util::stream::SplitSink;
 use futures_util::{SinkExt, StreamExt};
 use serde::{Deserialize, Serialize};
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
 use tauri::{AppHandle, Emitter};
+use tokio::net::TcpStream;
 use tokio::sync::Mutex;
-use tokio_tungstenite::{connect_async, tungstenite::Message};
+use tokio_tungstenite::{connect_async, tungstenite::Message, MaybeTlsStream, WebSocketStream};
+
+type WsWriteHalf = SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>;
 
 // Message from the server
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -41,13 +45,186 @@ fn decode_binary_tick(data: &[u8]) -> Option<TickMessage> {
     Some(TickMessage { symbol, price, ts })
 }
 
+/// Read just the 4-byte little-endian symbol index from a binary tick frame,
+/// without decoding the rest of the message. Used to keep per-symbol
+/// counters cheaply on every message.
+fn peek_binary_symbol_index(data: &[u8]) -> Option<usize> {
+    if data.len() < 4 {
+        return None;
+    }
+    Some(u32::from_le_bytes([data[0], data[1], data[2], data[3]]) as usize)
+}
+
+/// Read just the 8-byte little-endian timestamp (bytes 12-19) from a binary
+/// tick frame, without allocating a `TickMessage`. Used to measure latency
+/// on every message without paying full decode cost.
+fn peek_binary_timestamp(data: &[u8]) -> Option<i64> {
+    if data.len() < 20 {
+        return None;
+    }
+    Some(i64::from_le_bytes([
+        data[12], data[13], data[14], data[15],
+        data[16], data[17], data[18], data[19],
+    ]))
+}
+
+/// Extract the `"symbol":"XXX"` field from a text tick message with a
+/// `memchr`-based scan, mirroring `peek_binary_symbol_index` so the text
+/// path can also keep per-symbol counters cheaply on every message.
+fn peek_text_symbol_index(text: &str) -> Option<usize> {
+    let bytes = text.as_bytes();
+    let key_pos = memchr::memmem::find(bytes, b"\"symbol\":\"")?;
+    let value_start = key_pos + b"\"symbol\":\"".len();
+    let value_end = value_start + memchr::memchr(b'"', &bytes[value_start..])?;
+    let symbol = std::str::from_utf8(&bytes[value_start..value_end]).ok()?;
+    INDEX_TO_SYMBOL.iter().position(|&s| s == symbol)
+}
+
+/// Extract the `"ts":<number>` field from a text tick message with a
+/// `memchr`-based scan, avoiding a full `serde_json` parse on every message.
+fn peek_text_timestamp(text: &str) -> Option<i64> {
+    let bytes = text.as_bytes();
+    let key_pos = memchr::memmem::find(bytes, b"\"ts\":")?;
+    let mut digits_start = key_pos + 5;
+    while digits_start < bytes.len() && bytes[digits_start] == b' ' {
+        digits_start += 1;
+    }
+    let mut end = digits_start;
+    while end < bytes.len() && (bytes[end].is_ascii_digit() || (end == digits_start && bytes[end] == b'-')) {
+        end += 1;
+    }
+    std::str::from_utf8(&bytes[digits_start..end]).ok()?.parse().ok()
+}
+
+// How many messages to skip between full `TickMessage` decodes for the
+// `last_tick` display value; latency itself is measured independently (see
+// `SamplingMode`) since it's cheap to extract without a full decode.
+const LAST_TICK_SAMPLE_INTERVAL: u64 = 1000;
+
+/// Controls how often latency is measured per message, so users can compare
+/// sampled vs full-fidelity measurement overhead directly in the benchmark.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum SamplingMode {
+    EveryMessage,
+    Every(u64),
+}
+
+impl Default for SamplingMode {
+    fn default() -> Self {
+        SamplingMode::EveryMessage
+    }
+}
+
+impl SamplingMode {
+    fn should_sample(self, count: u64) -> bool {
+        match self {
+            SamplingMode::EveryMessage => true,
+            SamplingMode::Every(n) => n > 0 && count % n == 0,
+        }
+    }
+}
+
+// Number of linear sub-buckets per power-of-two magnitude. Splitting each
+// magnitude into SUB buckets keeps resolution high at low latencies (where
+// tick timing matters most) while a fixed-size array covers a huge dynamic
+// range.
+const HIST_SUB_BUCKETS: u32 = 8;
+const HIST_SUB_SHIFT: u32 = 3; // log2(HIST_SUB_BUCKETS)
+const HIST_NUM_BUCKETS: usize = 64 * HIST_SUB_BUCKETS as usize;
+
+/// Lock-free latency histogram with logarithmically spaced buckets.
+///
+/// Each sample is counted with a single `fetch_add` on an `AtomicU64`
+/// bucket, so recording a latency never blocks or allocates. Percentiles
+/// are derived by snapshotting the buckets and walking them cumulatively,
+/// which avoids storing per-sample vectors while still giving accurate
+/// tail numbers under millions of messages.
+pub struct LatencyHistogram {
+    buckets: Vec<AtomicU64>,
+}
+
+impl LatencyHistogram {
+    pub fn new() -> Self {
+        Self {
+            buckets: (0..HIST_NUM_BUCKETS).map(|_| AtomicU64::new(0)).collect(),
+        }
+    }
+
+    fn bucket_index(v: u64) -> usize {
+        if v < HIST_SUB_BUCKETS as u64 {
+            // Below the finest log-magnitude, the sub-bucket bits would
+            // overlap the magnitude bit itself and double-count it, so
+            // bucket linearly (one bucket per ms) to keep this range exact.
+            return v as usize;
+        }
+        let magnitude = 63 - (v | 1).leading_zeros();
+        let shift = magnitude.saturating_sub(HIST_SUB_SHIFT);
+        let sub = (v >> shift) & (HIST_SUB_BUCKETS as u64 - 1);
+        (magnitude * HIST_SUB_BUCKETS + sub as u32) as usize
+    }
+
+    // Representative midpoint value of a bucket, used when reporting a percentile.
+    fn bucket_value(idx: usize) -> u64 {
+        if idx < HIST_SUB_BUCKETS as usize {
+            return idx as u64;
+        }
+        let magnitude = (idx / HIST_SUB_BUCKETS as usize) as u32;
+        let sub = (idx % HIST_SUB_BUCKETS as usize) as u64;
+        let shift = magnitude.saturating_sub(HIST_SUB_SHIFT);
+        let sub_width = 1u64 << shift;
+        (1u64 << magnitude) + sub * sub_width + sub_width / 2
+    }
+
+    pub fn record(&self, latency_ms: u64) {
+        self.buckets[Self::bucket_index(latency_ms)].fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn reset(&self) {
+        for bucket in &self.buckets {
+            bucket.store(0, Ordering::Relaxed);
+        }
+    }
+
+    /// Snapshot and zero every bucket, then return the requested percentiles
+    /// (as midpoint latency values in ms) computed from that snapshot. This
+    /// is the read-and-clear done once per metrics interval (1 Hz on the
+    /// single-connection path, every `sample_rate_secs` in load-test mode) so
+    /// percentiles reflect only recent traffic.
+    pub fn percentiles(&self, qs: &[f64]) -> Vec<f64> {
+        let snapshot: Vec<u64> = self.buckets.iter().map(|b| b.swap(0, Ordering::Relaxed)).collect();
+        let total: u64 = snapshot.iter().sum();
+        if total == 0 {
+            return vec![0.0; qs.len()];
+        }
+
+        qs.iter()
+            .map(|&q| {
+                let target = ((total as f64 * q).ceil() as u64).max(1);
+                let mut cumulative = 0u64;
+                for (idx, count) in snapshot.iter().enumerate() {
+                    cumulative += count;
+                    if cumulative >= target {
+                        return Self::bucket_value(idx) as f64;
+                    }
+                }
+                Self::bucket_value(HIST_NUM_BUCKETS - 1) as f64
+            })
+            .collect()
+    }
+}
+
 // Metrics emitted to frontend
 #[derive(Debug, Clone, Serialize)]
 pub struct RustMetrics {
     pub messages_per_sec: u64,
     pub total_messages: u64,
     pub avg_latency_ms: f64,
+    pub p50_latency_ms: f64,
+    pub p95_latency_ms: f64,
+    pub p99_latency_ms: f64,
     pub last_tick: Option<TickMessage>,
+    pub reconnect_count: u64,
+    pub symbol_breakdown: Vec<SymbolCount>,
 }
 
 // Messages sent to the server (camelCase to match JS client format)
@@ -65,6 +242,15 @@ pub enum OutgoingMessage {
         avgLatencyMs: f64,
         p99LatencyMs: f64,
     },
+    #[serde(rename = "subscribe")]
+    Subscribe { clientId: String, symbols: Vec<String> },
+}
+
+// Per-symbol message count reported in the `RustMetrics` breakdown
+#[derive(Debug, Clone, Serialize)]
+pub struct SymbolCount {
+    pub symbol: String,
+    pub count: u64,
 }
 
 // Shared state for the WebSocket connection
@@ -74,8 +260,12 @@ pub struct WebSocketState {
     pub messages_this_second: AtomicU64,
     pub latency_sum_ms: AtomicU64,
     pub latency_count: AtomicU64,
+    pub latency_histogram: LatencyHistogram,
     pub last_tick: Mutex<Option<TickMessage>>,
     pub last_tick_update_counter: AtomicU64,
+    pub reconnect_count: AtomicU64,
+    // Per-symbol message counts, indexed the same way `decode_binary_tick` maps indices.
+    pub symbol_counts: [AtomicU64; INDEX_TO_SYMBOL.len()],
 }
 
 impl WebSocketState {
@@ -86,68 +276,107 @@ impl WebSocketState {
             messages_this_second: AtomicU64::new(0),
             latency_sum_ms: AtomicU64::new(0),
             latency_count: AtomicU64::new(0),
+            latency_histogram: LatencyHistogram::new(),
             last_tick: Mutex::new(None),
             last_tick_update_counter: AtomicU64::new(0),
+            reconnect_count: AtomicU64::new(0),
+            symbol_counts: std::array::from_fn(|_| AtomicU64::new(0)),
         }
     }
 
+    /// Per-symbol message counts since the last reset, for the `RustMetrics` breakdown.
+    pub fn symbol_breakdown(&self) -> Vec<SymbolCount> {
+        INDEX_TO_SYMBOL
+            .iter()
+            .zip(self.symbol_counts.iter())
+            .map(|(symbol, count)| SymbolCount {
+                symbol: symbol.to_string(),
+                count: count.load(Ordering::Relaxed),
+            })
+            .collect()
+    }
+
     pub fn reset(&self) {
         self.total_messages.store(0, Ordering::SeqCst);
         self.messages_this_second.store(0, Ordering::SeqCst);
         self.latency_sum_ms.store(0, Ordering::SeqCst);
         self.latency_count.store(0, Ordering::SeqCst);
+        self.latency_histogram.reset();
         self.last_tick_update_counter.store(0, Ordering::SeqCst);
+        self.reconnect_count.store(0, Ordering::SeqCst);
+        for count in &self.symbol_counts {
+            count.store(0, Ordering::SeqCst);
+        }
     }
 }
 
+/// Delay before the next reconnect attempt: exponential backoff from
+/// `base_delay_ms`, doubling per attempt and capped at `max_delay_ms`, with
+/// +/-20% jitter (seeded from the clock) so many connections retrying at
+/// once don't all land on the server in lockstep.
+fn backoff_delay_ms(attempt: u32, base_delay_ms: u64, max_delay_ms: u64) -> u64 {
+    let exp = base_delay_ms.saturating_mul(1u64 << attempt.saturating_sub(1).min(16));
+    let capped = exp.min(max_delay_ms).max(base_delay_ms);
+
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or(0);
+    let jitter_pct = (nanos % 41) as i64 - 20; // -20..=20
+    let jitter = (capped as i64 * jitter_pct) / 100;
+    (capped as i64 + jitter).max(0) as u64
+}
+
+async fn backoff_sleep(attempt: u32, base_delay_ms: u64, max_delay_ms: u64) {
+    let delay = backoff_delay_ms(attempt, base_delay_ms, max_delay_ms);
+    tokio::time::sleep(tokio::time::Duration::from_millis(delay)).await;
+}
+
+/// Record one latency sample (sender timestamp in ms) into both the running
+/// mean accumulator and the percentile histogram.
+fn record_latency(state: &WebSocketState, ts_ms: i64) {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as i64;
+    let latency = now.saturating_sub(ts_ms).max(0) as u64;
+    state.latency_sum_ms.fetch_add(latency, Ordering::Relaxed);
+    state.latency_count.fetch_add(1, Ordering::Relaxed);
+    state.latency_histogram.record(latency);
+}
+
 pub async fn connect_websocket(
     app_handle: AppHandle,
     state: Arc<WebSocketState>,
     url: String,
+    max_retries: u32,
+    base_delay_ms: u64,
+    max_delay_ms: u64,
+    symbols: Option<Vec<String>>,
+    sampling_mode: SamplingMode,
 ) {
     state.running.store(true, Ordering::SeqCst);
     state.reset();
 
     log::info!("Rust WebSocket connecting to {}", url);
 
-    let ws_result = connect_async(&url).await;
-    
-    let (mut write, mut read) = match ws_result {
-        Ok((ws_stream, _)) => ws_stream.split(),
-        Err(e) => {
-            log::error!("WebSocket connection failed: {}", e);
-            state.running.store(false, Ordering::SeqCst);
-            let _ = app_handle.emit("rust-ws-error", format!("Connection failed: {}", e));
-            return;
-        }
-    };
-
-    log::info!("Rust WebSocket connected!");
-    let _ = app_handle.emit("rust-ws-connected", ());
-
-    // Send identify message
-    let identify_msg = OutgoingMessage::Identify {
-        clientId: "tauri-rust".to_string(),
-    };
-    if let Ok(json) = serde_json::to_string(&identify_msg) {
-        let _ = write.send(Message::Text(json)).await;
-    }
+    // Holds whichever write half is currently live, so the metrics task
+    // (spawned once below) can keep sending stats across reconnects.
+    let write_slot: Arc<Mutex<Option<WsWriteHalf>>> = Arc::new(Mutex::new(None));
 
-    // Wrap write in Arc<Mutex> for sharing with metrics task
-    let write = Arc::new(Mutex::new(write));
-    let write_clone = write.clone();
-
-    // Spawn metrics emitter (1Hz) - also sends stats to server
+    // Spawn metrics emitter (1Hz) - also sends stats to server. Runs for the
+    // whole lifetime of the connection, surviving any reconnects below.
     let metrics_state = state.clone();
     let metrics_handle = app_handle.clone();
+    let metrics_write = write_slot.clone();
     let metrics_task = tokio::spawn(async move {
         let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(1));
         while metrics_state.running.load(Ordering::SeqCst) {
             interval.tick().await;
-            
+
             let msg_per_sec = metrics_state.messages_this_second.swap(0, Ordering::SeqCst);
             let total = metrics_state.total_messages.load(Ordering::SeqCst);
-            
+
             let latency_sum = metrics_state.latency_sum_ms.swap(0, Ordering::SeqCst);
             let latency_count = metrics_state.latency_count.swap(0, Ordering::SeqCst);
             let avg_latency = if latency_count > 0 {
@@ -158,94 +387,474 @@ pub async fn connect_websocket(
 
             let last_tick = metrics_state.last_tick.lock().await.clone();
 
+            let percentiles = metrics_state.latency_histogram.percentiles(&[0.5, 0.95, 0.99]);
+            let (p50_latency, p95_latency, p99_latency) = (percentiles[0], percentiles[1], percentiles[2]);
+
             let metrics = RustMetrics {
                 messages_per_sec: msg_per_sec,
                 total_messages: total,
                 avg_latency_ms: avg_latency,
+                p50_latency_ms: p50_latency,
+                p95_latency_ms: p95_latency,
+                p99_latency_ms: p99_latency,
                 last_tick,
+                reconnect_count: metrics_state.reconnect_count.load(Ordering::Relaxed),
+                symbol_breakdown: metrics_state.symbol_breakdown(),
             };
 
             // Emit to frontend
             let _ = metrics_handle.emit("rust-ws-metrics", metrics);
 
-            // Send stats to server
+            // Send stats to server, if currently connected
             let stats_msg = OutgoingMessage::Stats {
                 clientId: "tauri-rust".to_string(),
                 messagesPerSec: msg_per_sec,
                 totalMessages: total,
                 avgLatencyMs: avg_latency,
-                p99LatencyMs: 0.0, // Not tracked in Rust version
+                p99LatencyMs: p99_latency,
             };
             if let Ok(json) = serde_json::to_string(&stats_msg) {
-                let mut w = write_clone.lock().await;
-                let _ = w.send(Message::Text(json)).await;
+                if let Some(w) = metrics_write.lock().await.as_mut() {
+                    let _ = w.send(Message::Text(json)).await;
+                }
+            }
+        }
+    });
+
+    let mut attempt: u32 = 0;
+    while state.running.load(Ordering::SeqCst) {
+        if attempt > 0 {
+            let _ = app_handle.emit("rust-ws-reconnecting", attempt);
+        }
+
+        let (mut write, mut read) = match connect_async(&url).await {
+            Ok((ws_stream, _)) => ws_stream.split(),
+            Err(e) => {
+                log::error!("WebSocket connection failed (attempt {}): {}", attempt + 1, e);
+                attempt += 1;
+                if max_retries > 0 && attempt >= max_retries {
+                    let _ = app_handle.emit("rust-ws-error", format!("Connection failed: {}", e));
+                    break;
+                }
+                backoff_sleep(attempt, base_delay_ms, max_delay_ms).await;
+                continue;
+            }
+        };
+
+        log::info!("Rust WebSocket connected!");
+        attempt = 0;
+        let _ = app_handle.emit("rust-ws-connected", ());
+
+        // Send identify message (also re-sent after every reconnect)
+        let identify_msg = OutgoingMessage::Identify {
+            clientId: "tauri-rust".to_string(),
+        };
+        if let Ok(json) = serde_json::to_string(&identify_msg) {
+            let _ = write.send(Message::Text(json)).await;
+        }
+
+        // Ask the server to filter the feed down to a subset of symbols, if requested
+        if let Some(symbols) = &symbols {
+            let subscribe_msg = OutgoingMessage::Subscribe {
+                clientId: "tauri-rust".to_string(),
+                symbols: symbols.clone(),
+            };
+            if let Ok(json) = serde_json::to_string(&subscribe_msg) {
+                let _ = write.send(Message::Text(json)).await;
+            }
+        }
+
+        *write_slot.lock().await = Some(write);
+
+        // Read messages - optimized for high throughput
+        while state.running.load(Ordering::Relaxed) {
+            match read.next().await {
+                Some(Ok(msg)) => {
+                    // Handle both text (JSON) and binary messages
+                    let tick_opt: Option<TickMessage> = match &msg {
+                        Message::Text(text) => {
+                            // Count every message
+                            let count = state.total_messages.fetch_add(1, Ordering::Relaxed);
+                            state.messages_this_second.fetch_add(1, Ordering::Relaxed);
+
+                            // Cheap per-symbol counter on every message (no full decode)
+                            if let Some(symbol_index) = peek_text_symbol_index(text) {
+                                if let Some(counter) = state.symbol_counts.get(symbol_index) {
+                                    counter.fetch_add(1, Ordering::Relaxed);
+                                }
+                            }
+
+                            // Measure latency per `sampling_mode`, without a full JSON parse
+                            if sampling_mode.should_sample(count) {
+                                if let Some(ts) = peek_text_timestamp(text) {
+                                    record_latency(&state, ts);
+                                }
+                            }
+
+                            // Only build the full TickMessage on a throttled cadence, for last_tick
+                            if count % LAST_TICK_SAMPLE_INTERVAL == 0 {
+                                serde_json::from_str::<TickMessage>(text).ok()
+                            } else {
+                                None
+                            }
+                        }
+                        Message::Binary(data) => {
+                            // Count every message
+                            let count = state.total_messages.fetch_add(1, Ordering::Relaxed);
+                            state.messages_this_second.fetch_add(1, Ordering::Relaxed);
+
+                            // Cheap per-symbol counter on every message (no full decode)
+                            if let Some(symbol_index) = peek_binary_symbol_index(data) {
+                                if let Some(counter) = state.symbol_counts.get(symbol_index) {
+                                    counter.fetch_add(1, Ordering::Relaxed);
+                                }
+                            }
+
+                            // Measure latency per `sampling_mode`, reading just the timestamp bytes
+                            if sampling_mode.should_sample(count) {
+                                if let Some(ts) = peek_binary_timestamp(data) {
+                                    record_latency(&state, ts);
+                                }
+                            }
+
+                            // Only build the full TickMessage on a throttled cadence, for last_tick
+                            if count % LAST_TICK_SAMPLE_INTERVAL == 0 {
+                                decode_binary_tick(data)
+                            } else {
+                                None
+                            }
+                        }
+                        _ => None,
+                    };
+
+                    // Update last_tick with whatever we fully decoded, if anything
+                    if let Some(tick) = tick_opt {
+                        if let Ok(mut last) = state.last_tick.try_lock() {
+                            *last = Some(tick);
+                        }
+                    }
+                }
+                Some(Err(e)) => {
+                    log::error!("WebSocket error: {}", e);
+                    break;
+                }
+                None => {
+                    log::info!("WebSocket stream ended");
+                    break;
+                }
             }
         }
+
+        *write_slot.lock().await = None;
+
+        if !state.running.load(Ordering::SeqCst) {
+            break;
+        }
+
+        // Connection dropped but we're still meant to be running: reconnect.
+        state.reconnect_count.fetch_add(1, Ordering::Relaxed);
+        attempt += 1;
+        log::info!("Rust WebSocket disconnected, reconnecting (attempt {})", attempt);
+        backoff_sleep(attempt, base_delay_ms, max_delay_ms).await;
+    }
+
+    state.running.store(false, Ordering::SeqCst);
+    metrics_task.abort();
+    let _ = app_handle.emit("rust-ws-disconnected", ());
+    log::info!("Rust WebSocket disconnected");
+}
+
+// ---------------------------------------------------------------------
+// Load-test mode: spawn many concurrent connections against the same URL
+// and aggregate their counters into one merged set of metrics, modeled on
+// a typical ws load-generator (e.g. artillery/ws-bench style tools).
+// ---------------------------------------------------------------------
+
+/// Config for a load-test run.
+#[derive(Debug, Clone, Deserialize)]
+pub struct LoadTestConfig {
+    pub url: String,
+    pub connections: usize,
+    pub warmup_secs: u64,
+    pub sample_rate_secs: u64,
+    pub binary: bool,
+    pub payload_kb: Option<usize>,
+}
+
+// Aggregated metrics emitted to the frontend while a load test is running
+#[derive(Debug, Clone, Serialize)]
+pub struct LoadTestMetrics {
+    pub connections: usize,
+    pub messages_per_sec: u64,
+    pub total_messages: u64,
+    pub avg_latency_ms: f64,
+    pub p50_latency_ms: f64,
+    pub p95_latency_ms: f64,
+    pub p99_latency_ms: f64,
+}
+
+// Shared state for an in-flight load test
+pub struct LoadTestState {
+    pub running: AtomicBool,
+    // Merged counters across every connection; reuses `WebSocketState` since
+    // it already models exactly the counters a load test needs to aggregate.
+    pub counters: Arc<WebSocketState>,
+    pub handles: Mutex<Vec<tokio::task::JoinHandle<()>>>,
+}
+
+impl LoadTestState {
+    pub fn new() -> Self {
+        Self {
+            running: AtomicBool::new(false),
+            counters: Arc::new(WebSocketState::new()),
+            handles: Mutex::new(Vec::new()),
+        }
+    }
+}
+
+pub async fn start_load_test(app_handle: AppHandle, state: Arc<LoadTestState>, config: LoadTestConfig) {
+    state.running.store(true, Ordering::SeqCst);
+    state.counters.reset();
+    state.handles.lock().await.clear();
+
+    let warmup_deadline =
+        tokio::time::Instant::now() + tokio::time::Duration::from_secs(config.warmup_secs);
+
+    log::info!(
+        "Starting load test: {} connections against {}",
+        config.connections, config.url
+    );
+
+    for conn_id in 0..config.connections {
+        let conn_state = state.clone();
+        let conn_handle = app_handle.clone();
+        let url = config.url.clone();
+        let binary = config.binary;
+        let payload_kb = config.payload_kb;
+        let handle = tokio::spawn(async move {
+            run_load_test_connection(
+                conn_handle,
+                conn_state,
+                conn_id,
+                url,
+                warmup_deadline,
+                binary,
+                payload_kb,
+            )
+            .await;
+        });
+        // Push directly (rather than collecting into a local Vec and
+        // assigning once) so it can't race with a connection's own publish
+        // task also pushing its handle into `state.handles`.
+        state.handles.lock().await.push(handle);
+    }
+
+    // Aggregate emitter: reports merged metrics every `sample_rate_secs`.
+    let metrics_state = state.clone();
+    let metrics_handle = app_handle.clone();
+    let sample_rate = config.sample_rate_secs.max(1);
+    let connections = config.connections;
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(sample_rate));
+        while metrics_state.running.load(Ordering::SeqCst) {
+            interval.tick().await;
+
+            // `messages_this_second` actually accumulates over the whole
+            // `sample_rate` window here (the window is the tick interval,
+            // not always 1s), so divide down to a true per-second rate.
+            let msg_per_sec = metrics_state.counters.messages_this_second.swap(0, Ordering::SeqCst) / sample_rate;
+            let total = metrics_state.counters.total_messages.load(Ordering::SeqCst);
+            let latency_sum = metrics_state.counters.latency_sum_ms.swap(0, Ordering::SeqCst);
+            let latency_count = metrics_state.counters.latency_count.swap(0, Ordering::SeqCst);
+            let avg_latency = if latency_count > 0 {
+                latency_sum as f64 / latency_count as f64
+            } else {
+                0.0
+            };
+            let percentiles = metrics_state.counters.latency_histogram.percentiles(&[0.5, 0.95, 0.99]);
+
+            let metrics = LoadTestMetrics {
+                connections,
+                messages_per_sec: msg_per_sec,
+                total_messages: total,
+                avg_latency_ms: avg_latency,
+                p50_latency_ms: percentiles[0],
+                p95_latency_ms: percentiles[1],
+                p99_latency_ms: percentiles[2],
+            };
+            let _ = metrics_handle.emit("load-test-metrics", metrics);
+        }
     });
+}
+
+pub async fn stop_load_test(state: Arc<LoadTestState>) {
+    state.running.store(false, Ordering::SeqCst);
+    let mut handles = state.handles.lock().await;
+    for handle in handles.drain(..) {
+        handle.abort();
+    }
+}
+
+async fn run_load_test_connection(
+    app_handle: AppHandle,
+    state: Arc<LoadTestState>,
+    conn_id: usize,
+    url: String,
+    warmup_deadline: tokio::time::Instant,
+    binary: bool,
+    payload_kb: Option<usize>,
+) {
+    let ws_result = connect_async(&url).await;
+    let (mut write, mut read) = match ws_result {
+        Ok((ws_stream, _)) => ws_stream.split(),
+        Err(e) => {
+            log::error!("Load test connection {} failed to connect: {}", conn_id, e);
+            return;
+        }
+    };
+
+    let identify_msg = OutgoingMessage::Identify {
+        clientId: format!("tauri-load-{}", conn_id),
+    };
+    if let Ok(json) = serde_json::to_string(&identify_msg) {
+        let _ = write.send(Message::Text(json)).await;
+    }
+
+    // Wrap write in Arc<Mutex> so an optional publish task can share it.
+    let write = Arc::new(Mutex::new(write));
+
+    if let Some(payload_kb) = payload_kb {
+        let payload = vec![0u8; payload_kb * 1024];
+        let writer = write.clone();
+        let publish_state = state.clone();
+        let publish_handle = tokio::spawn(async move {
+            let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(1));
+            while publish_state.running.load(Ordering::Relaxed) {
+                interval.tick().await;
+                let msg = if binary {
+                    Message::Binary(payload.clone())
+                } else {
+                    Message::Text(String::from_utf8_lossy(&payload).into_owned())
+                };
+                if writer.lock().await.send(msg).await.is_err() {
+                    break;
+                }
+            }
+        });
+        // Register so `stop_load_test` aborts this task too, not just the
+        // connection's own read-loop task.
+        state.handles.lock().await.push(publish_handle);
+    }
 
-    // Read messages - optimized for high throughput
     while state.running.load(Ordering::Relaxed) {
         match read.next().await {
             Some(Ok(msg)) => {
-                // Handle both text (JSON) and binary messages
-                let tick_opt: Option<TickMessage> = match &msg {
+                // Drain (but don't count) messages until the warm-up window elapses.
+                if tokio::time::Instant::now() < warmup_deadline {
+                    continue;
+                }
+
+                // Measure latency on every message (not a throttled sample) so the
+                // merged histogram/percentiles are trustworthy, mirroring the cheap
+                // peek helpers used on the single-connection path.
+                match &msg {
                     Message::Text(text) => {
-                        // Count every message
-                        let count = state.total_messages.fetch_add(1, Ordering::Relaxed);
-                        state.messages_this_second.fetch_add(1, Ordering::Relaxed);
-                        
-                        // Only parse JSON every 1000 messages to reduce overhead
-                        if count % 1000 == 0 {
-                            serde_json::from_str::<TickMessage>(text).ok()
-                        } else {
-                            None
+                        state.counters.total_messages.fetch_add(1, Ordering::Relaxed);
+                        state.counters.messages_this_second.fetch_add(1, Ordering::Relaxed);
+                        if let Some(ts) = peek_text_timestamp(text) {
+                            record_latency(&state.counters, ts);
                         }
                     }
                     Message::Binary(data) => {
-                        // Count every message
-                        let count = state.total_messages.fetch_add(1, Ordering::Relaxed);
-                        state.messages_this_second.fetch_add(1, Ordering::Relaxed);
-                        
-                        // Only decode binary every 1000 messages to reduce overhead
-                        if count % 1000 == 0 {
-                            decode_binary_tick(data)
-                        } else {
-                            None
+                        state.counters.total_messages.fetch_add(1, Ordering::Relaxed);
+                        state.counters.messages_this_second.fetch_add(1, Ordering::Relaxed);
+                        if let Some(ts) = peek_binary_timestamp(data) {
+                            record_latency(&state.counters, ts);
                         }
                     }
-                    _ => None,
-                };
-                
-                // Process tick if we decoded one
-                if let Some(tick) = tick_opt {
-                    let now = std::time::SystemTime::now()
-                        .duration_since(std::time::UNIX_EPOCH)
-                        .unwrap()
-                        .as_millis() as u64;
-                    
-                    let latency = now.saturating_sub(tick.ts);
-                    state.latency_sum_ms.fetch_add(latency, Ordering::Relaxed);
-                    state.latency_count.fetch_add(1, Ordering::Relaxed);
-                    
-                    // Update last_tick
-                    if let Ok(mut last) = state.last_tick.try_lock() {
-                        *last = Some(tick);
-                    }
+                    _ => {}
                 }
             }
             Some(Err(e)) => {
-                log::error!("WebSocket error: {}", e);
-                break;
-            }
-            None => {
-                log::info!("WebSocket stream ended");
+                log::error!("Load test connection {} error: {}", conn_id, e);
                 break;
             }
+            None => break,
         }
     }
 
-    state.running.store(false, Ordering::SeqCst);
-    metrics_task.abort();
-    let _ = app_handle.emit("rust-ws-disconnected", ());
-    log::info!("Rust WebSocket disconnected");
+    let _ = app_handle.emit("load-test-connection-closed", conn_id);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn peek_text_symbol_index_matches_binary_index() {
+        let text = r#"{"symbol":"ETH","price":1234.5,"ts":1000}"#;
+        assert_eq!(peek_text_symbol_index(text), Some(1)); // ETH is index 1
+        assert_eq!(peek_text_symbol_index(r#"{"symbol":"???"}"#), None);
+    }
+
+    #[test]
+    fn peek_text_timestamp_handles_compact_and_spaced_json() {
+        assert_eq!(
+            peek_text_timestamp(r#"{"symbol":"BTC","price":1.0,"ts":1234}"#),
+            Some(1234)
+        );
+        // A space after the colon (common with pretty-printed JSON) must
+        // still be parsed, not silently dropped.
+        assert_eq!(
+            peek_text_timestamp(r#"{"symbol":"BTC","price":1.0,"ts": 1234}"#),
+            Some(1234)
+        );
+        assert_eq!(peek_text_timestamp(r#"{"symbol":"BTC"}"#), None);
+    }
+
+    #[test]
+    fn peek_binary_timestamp_reads_bytes_12_to_19() {
+        let mut data = vec![0u8; 20];
+        data[0..4].copy_from_slice(&1u32.to_le_bytes());
+        data[12..20].copy_from_slice(&9999i64.to_le_bytes());
+        assert_eq!(peek_binary_timestamp(&data), Some(9999));
+        assert_eq!(peek_binary_symbol_index(&data), Some(1));
+        assert_eq!(peek_binary_timestamp(&[0u8; 10]), None);
+    }
+
+    #[test]
+    fn backoff_delay_grows_and_caps() {
+        let base = 250;
+        let cap = 30_000;
+        // Un-jittered magnitude should roughly double each attempt, capped at `cap`.
+        assert!(backoff_delay_ms(1, base, cap) >= (base as f64 * 0.8) as u64);
+        assert!(backoff_delay_ms(1, base, cap) <= (base as f64 * 1.2) as u64);
+        // Attempt 3 is two doublings past the base: 250 * 4 = 1000ms, +/-20%.
+        assert!(backoff_delay_ms(3, base, cap) >= 800);
+        assert!(backoff_delay_ms(3, base, cap) <= 1200);
+        // Far past the cap the delay sits at `cap`, +/-20%.
+        assert!(backoff_delay_ms(19, base, cap) >= (cap as f64 * 0.8) as u64);
+        for attempt in 1..20 {
+            let delay = backoff_delay_ms(attempt, base, cap);
+            assert!(delay <= (cap as f64 * 1.2) as u64);
+        }
+    }
+
+    #[test]
+    fn histogram_percentiles_low_latency_samples_are_exact() {
+        let hist = LatencyHistogram::new();
+        for v in [1, 1, 2, 3, 5, 7] {
+            hist.record(v);
+        }
+        let percentiles = hist.percentiles(&[0.5, 1.0]);
+        // 6 samples sorted: 1, 1, 2, 3, 5, 7
+        assert_eq!(percentiles[0], 2.0); // p50 -> 3rd sample
+        assert_eq!(percentiles[1], 7.0); // p100 -> last sample, exact below 8ms
+    }
+
+    #[test]
+    fn histogram_percentiles_empty_is_zero() {
+        let hist = LatencyHistogram::new();
+        assert_eq!(hist.percentiles(&[0.5, 0.99]), vec![0.0, 0.0]);
+    }
 }